@@ -0,0 +1,134 @@
+//! HMM/Viterbi segmentation for out-of-vocabulary Chinese runs, tagging each
+//! character B(egin)/M(iddle)/E(nd)/S(ingle).
+
+use std::collections::HashMap;
+
+static HMM_MODEL: &str = include_str!("hmm_model.dic");
+
+const B: usize = 0;
+const M: usize = 1;
+const E: usize = 2;
+const S: usize = 3;
+const NUM_STATES: usize = 4;
+
+/// `(from, to)` pairs that are legal in the B/M/E/S tagging scheme.
+const LEGAL_TRANSITIONS: [(usize, usize); 8] = [
+    (B, M),
+    (B, E),
+    (M, M),
+    (M, E),
+    (E, B),
+    (E, S),
+    (S, B),
+    (S, S),
+];
+
+/// Log-probability floor for characters never seen during training.
+const EMIT_FLOOR: f32 = -20.0;
+
+#[derive(Debug)]
+pub(crate) struct HmmModel {
+    start: [f32; NUM_STATES],
+    trans: [[f32; NUM_STATES]; NUM_STATES],
+    emit: [HashMap<char, f32>; NUM_STATES],
+}
+
+impl HmmModel {
+    pub(crate) fn load_embedded() -> Self {
+        Self::parse(HMM_MODEL)
+    }
+
+    fn parse(data: &str) -> Self {
+        let mut start = [f32::NEG_INFINITY; NUM_STATES];
+        let mut trans = [[f32::NEG_INFINITY; NUM_STATES]; NUM_STATES];
+        let mut emit: [HashMap<char, f32>; NUM_STATES] = [
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::new(),
+        ];
+        for line in data.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["START", s, p] => start[state_index(s)] = p.parse().unwrap(),
+                ["TRANS", from, to, p] => {
+                    trans[state_index(from)][state_index(to)] = p.parse().unwrap()
+                }
+                ["EMIT", s, chr, p] => {
+                    let chr = chr.chars().next().unwrap();
+                    emit[state_index(s)].insert(chr, p.parse().unwrap());
+                }
+                _ => continue,
+            }
+        }
+        HmmModel { start, trans, emit }
+    }
+
+    fn emit_prob(&self, state: usize, chr: char) -> f32 {
+        self.emit[state].get(&chr).copied().unwrap_or(EMIT_FLOOR)
+    }
+
+    /// Viterbi-decodes `chars` into B/M/E/S tags and returns the resulting
+    /// `(start, end)` word spans (`end` exclusive), in order.
+    pub(crate) fn segment(&self, chars: &[char]) -> Vec<(usize, usize)> {
+        let n = chars.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut delta = vec![[f32::NEG_INFINITY; NUM_STATES]; n];
+        let mut back = vec![[0usize; NUM_STATES]; n];
+        for (s, delta0) in delta[0].iter_mut().enumerate() {
+            *delta0 = self.start[s] + self.emit_prob(s, chars[0]);
+        }
+        for t in 1..n {
+            for s in 0..NUM_STATES {
+                let mut best = f32::NEG_INFINITY;
+                let mut best_prev = 0;
+                for &(from, to) in LEGAL_TRANSITIONS.iter() {
+                    if to != s {
+                        continue;
+                    }
+                    let score = delta[t - 1][from] + self.trans[from][to];
+                    if score > best {
+                        best = score;
+                        best_prev = from;
+                    }
+                }
+                delta[t][s] = best + self.emit_prob(s, chars[t]);
+                back[t][s] = best_prev;
+            }
+        }
+        let last = n - 1;
+        let mut state = if delta[last][E] >= delta[last][S] { E } else { S };
+        let mut tags = vec![0usize; n];
+        tags[last] = state;
+        for t in (0..last).rev() {
+            state = back[t + 1][state];
+            tags[t] = state;
+        }
+        let mut spans = Vec::new();
+        let mut start = 0;
+        for (i, &tag) in tags.iter().enumerate() {
+            if tag == E || tag == S {
+                spans.push((start, i + 1));
+                start = i + 1;
+            }
+        }
+        if start < n {
+            // A malformed tag sequence shouldn't happen with a complete model,
+            // but emit the remainder as a single word rather than dropping chars.
+            spans.push((start, n));
+        }
+        spans
+    }
+}
+
+fn state_index(s: &str) -> usize {
+    match s {
+        "B" => B,
+        "M" => M,
+        "E" => E,
+        "S" => S,
+        _ => panic!("unknown HMM state: {}", s),
+    }
+}
@@ -0,0 +1,174 @@
+//! Keyword extraction built on top of [`MMSeg::cut`](crate::MMSeg::cut):
+//! [`TfIdf`] and [`TextRank`].
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::MMSeg;
+
+static IDF_DICT: &str = include_str!("idf.dic");
+static STOP_WORDS: &str = include_str!("stopwords.dic");
+
+/// Extracts the top-scoring keywords from `text`.
+pub trait KeywordExtract {
+    /// Returns up to `top_k` `(word, score)` pairs, highest score first.
+    ///
+    /// `allowed_pos` restricts results to the given part-of-speech tags.
+    /// This crate does not yet perform POS tagging, so `allowed_pos` is
+    /// currently accepted for API compatibility only and has no effect.
+    fn extract_tags(&self, text: &str, top_k: usize, allowed_pos: &[&str]) -> Vec<(String, f32)>;
+}
+
+fn load_stop_words() -> HashSet<String> {
+    STOP_WORDS.lines().map(|w| w.trim().to_string()).collect()
+}
+
+fn is_ascii_word(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// TF-IDF keyword extraction over terms segmented by [`MMSeg::cut`].
+pub struct TfIdf<'a> {
+    mmseg: &'a MMSeg,
+    idf: HashMap<String, f32>,
+    median_idf: f32,
+    stop_words: HashSet<String>,
+}
+
+impl<'a> TfIdf<'a> {
+    pub fn new(mmseg: &'a MMSeg) -> Self {
+        let idf = parse_idf_dict(IDF_DICT);
+        let median_idf = median(&idf);
+        TfIdf {
+            mmseg,
+            idf,
+            median_idf,
+            stop_words: load_stop_words(),
+        }
+    }
+}
+
+fn parse_idf_dict(data: &str) -> HashMap<String, f32> {
+    let mut idf = HashMap::new();
+    for line in data.lines() {
+        let mut parts = line.split_whitespace();
+        if let (Some(word), Some(value)) = (parts.next(), parts.next()) {
+            if let Ok(value) = value.parse() {
+                idf.insert(word.to_string(), value);
+            }
+        }
+    }
+    idf
+}
+
+fn median(idf: &HashMap<String, f32>) -> f32 {
+    let mut values: Vec<f32> = idf.values().copied().collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    values[values.len() / 2]
+}
+
+impl<'a> KeywordExtract for TfIdf<'a> {
+    fn extract_tags(&self, text: &str, top_k: usize, _allowed_pos: &[&str]) -> Vec<(String, f32)> {
+        let tokens = self.mmseg.cut(text);
+        let mut freq: HashMap<&str, u32> = HashMap::new();
+        let mut total = 0u32;
+        for token in &tokens {
+            if token.chars().count() <= 1 || is_ascii_word(token) || self.stop_words.contains(token)
+            {
+                continue;
+            }
+            *freq.entry(token.as_str()).or_insert(0) += 1;
+            total += 1;
+        }
+        let mut scores: Vec<(String, f32)> = freq
+            .into_iter()
+            .map(|(word, count)| {
+                let tf = count as f32 / total.max(1) as f32;
+                let idf = self.idf.get(word).copied().unwrap_or(self.median_idf);
+                (word.to_string(), tf * idf)
+            })
+            .collect();
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        scores.truncate(top_k);
+        scores
+    }
+}
+
+/// TextRank keyword extraction: a weighted PageRank over a co-occurrence
+/// graph of terms segmented by [`MMSeg::cut`].
+pub struct TextRank<'a> {
+    mmseg: &'a MMSeg,
+    stop_words: HashSet<String>,
+    window_size: usize,
+    damping: f32,
+    iterations: usize,
+}
+
+impl<'a> TextRank<'a> {
+    pub fn new(mmseg: &'a MMSeg) -> Self {
+        TextRank {
+            mmseg,
+            stop_words: load_stop_words(),
+            window_size: 5,
+            damping: 0.85,
+            iterations: 10,
+        }
+    }
+}
+
+impl<'a> KeywordExtract for TextRank<'a> {
+    fn extract_tags(&self, text: &str, top_k: usize, _allowed_pos: &[&str]) -> Vec<(String, f32)> {
+        let tokens: Vec<String> = self
+            .mmseg
+            .cut(text)
+            .into_iter()
+            .filter(|t| t.chars().count() > 1 && !is_ascii_word(t) && !self.stop_words.contains(t))
+            .collect();
+
+        let mut graph: HashMap<&str, HashMap<&str, f32>> = HashMap::new();
+        for i in 0..tokens.len() {
+            for j in (i + 1)..tokens.len().min(i + self.window_size) {
+                if tokens[i] == tokens[j] {
+                    continue;
+                }
+                *graph
+                    .entry(&tokens[i])
+                    .or_default()
+                    .entry(&tokens[j])
+                    .or_insert(0.0) += 1.0;
+                *graph
+                    .entry(&tokens[j])
+                    .or_default()
+                    .entry(&tokens[i])
+                    .or_insert(0.0) += 1.0;
+            }
+        }
+
+        let mut weighted_score: HashMap<&str, f32> = graph.keys().map(|&v| (v, 1.0)).collect();
+        for _ in 0..self.iterations {
+            let mut next = HashMap::with_capacity(weighted_score.len());
+            for (&v, neighbors) in &graph {
+                let mut sum = 0.0;
+                for (&u, &w_vu) in neighbors {
+                    let out_sum: f32 = graph[u].values().sum();
+                    if out_sum > 0.0 {
+                        sum += (w_vu / out_sum) * weighted_score[u];
+                    }
+                }
+                next.insert(v, (1.0 - self.damping) + self.damping * sum);
+            }
+            weighted_score = next;
+        }
+
+        let mut scores: Vec<(String, f32)> = weighted_score
+            .into_iter()
+            .map(|(word, score)| (word.to_string(), score))
+            .collect();
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        scores.truncate(top_k);
+        scores
+    }
+}
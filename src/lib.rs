@@ -1,11 +1,22 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::BinaryHeap;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
 use arrayvec::ArrayVec;
 
+#[cfg(feature = "hmm")]
+mod hmm;
+#[cfg(feature = "hmm")]
+use hmm::HmmModel;
+
+mod keywords;
+pub use keywords::{KeywordExtract, TextRank, TfIdf};
+
+mod trie;
+use trie::Trie;
+
 #[cfg(feature = "embed-dict")]
 static CHARS_DICT: &str = include_str!("chars.dic");
 #[cfg(feature = "embed-dict")]
@@ -83,17 +94,79 @@ impl Chunk {
     }
 }
 
+/// A segmented word with its char (`start`/`end`) and byte (`start_byte`/`end_byte`) offsets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub word: String,
+    pub start: usize,
+    pub end: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// Default beam width for [`MMSeg::cut_beam`].
+const DEFAULT_BEAM_WIDTH: usize = 5;
+
+/// Log-probability floor for a position with no dictionary match at all
+/// (the OOV placeholder), clearly worse than any real word.
+const BEAM_ZERO_FREQ_FLOOR: f32 = -5.0;
+
+/// Dominant per-word term in [`MMSeg::beam_search`]'s path score: matching
+/// one (however long) word costs less than matching several narrower ones
+/// over the same span, mirroring `avg_word_len`'s "fewer, longer words"
+/// preference in [`get_chinese_words_complex`](MMSeg::get_chinese_words_complex).
+/// Needed because `load_dict` (and `add_word` without an explicit `freq`)
+/// leave every multi-char word's `freq` at `0`, so scoring purely by `ln(freq)` would otherwise value a
+/// real multi-char match the same as the "no match" placeholder and let a
+/// run of high-frequency single characters always outscore it.
+const BEAM_WORD_COUNT_PENALTY: f32 = -1.0;
+
+/// Weight applied to a real word's `ln(freq)` (for `freq > 0`). Each weighted
+/// term is at most about `0.22` (`ln(u32::MAX) * 0.01`), so the frequency
+/// terms of up to four words cannot outweigh one extra word's
+/// [`BEAM_WORD_COUNT_PENALTY`]; for paths of five or more words they can.
+const BEAM_FREQ_TIEBREAK_WEIGHT: f32 = 0.01;
+
+/// A partial segmentation path explored by [`MMSeg::cut_beam`]'s beam search.
+#[derive(Debug, Clone)]
+struct Sequence {
+    words: Vec<Word>,
+    log_score: f32,
+}
+
+impl PartialEq for Sequence {
+    fn eq(&self, other: &Self) -> bool {
+        self.log_score == other.log_score
+    }
+}
+
+impl Eq for Sequence {}
+
+impl PartialOrd for Sequence {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Sequence {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.log_score.partial_cmp(&other.log_score).unwrap_or(Ordering::Equal)
+    }
+}
+
 #[derive(Debug)]
 pub struct MMSeg {
-    words: HashMap<String, u32>,
-    max_word_len: u32,
+    words: Trie,
+    #[cfg(feature = "hmm")]
+    hmm_model: HmmModel,
 }
 
 impl MMSeg {
     pub fn new() -> Self {
         let mut seg = Self {
-            words: HashMap::new(),
-            max_word_len: 0,
+            words: Trie::new(),
+            #[cfg(feature = "hmm")]
+            hmm_model: HmmModel::load_embedded(),
         };
         #[cfg(feature = "embed-dict")]
         seg.load_embed_dict().unwrap();
@@ -117,11 +190,7 @@ impl MMSeg {
             {
                 let parts: Vec<&str> = buf.split(' ').collect();
                 let freq: u32 = parts[0].parse().unwrap();
-                let chr = parts[1].trim().to_string();
-                let word_len = chr.chars().count() as u32;
-                if word_len > self.max_word_len {
-                    self.max_word_len = word_len;
-                }
+                let chr = parts[1].trim();
                 self.words.insert(chr, freq);
             }
             buf.clear();
@@ -129,11 +198,7 @@ impl MMSeg {
         while words_dict.read_line(&mut buf)? > 0 {
             {
                 let parts: Vec<&str> = buf.split(' ').collect();
-                let word_len: u32 = parts[0].parse().unwrap();
-                let chr = parts[1].trim().to_string();
-                if word_len > self.max_word_len {
-                    self.max_word_len = word_len;
-                }
+                let chr = parts[1].trim();
                 self.words.insert(chr, 0);
             }
             buf.clear();
@@ -154,6 +219,28 @@ impl MMSeg {
         )
     }
 
+    /// Inserts `word` into the dictionary, or updates its frequency if already present.
+    /// Uses `suggest_freq` if `freq` is `None`.
+    pub fn add_word(&mut self, word: &str, freq: Option<u32>) {
+        let freq = freq.unwrap_or_else(|| self.suggest_freq(word));
+        self.words.insert(word, freq);
+    }
+
+    /// Removes `word` from the dictionary.
+    pub fn remove_word(&mut self, word: &str) {
+        self.words.remove(word);
+    }
+
+    /// Single-char words get a frequency one higher than the current max single-char
+    /// frequency, so they win `get_chinese_words_complex`'s `word_freq()` tie-break;
+    /// multi-char words get `0`, matching `load_dict`'s `words_dict` entries.
+    pub fn suggest_freq(&self, word: &str) -> u32 {
+        if word.chars().count() != 1 {
+            return 0;
+        }
+        self.words.max_single_char_freq().saturating_add(1)
+    }
+
     pub fn cut_simple(&self, text: &str) -> Vec<String> {
         self.cut_internal(text, true)
     }
@@ -162,13 +249,81 @@ impl MMSeg {
         self.cut_internal(text, false)
     }
 
+    /// Like jieba's full mode: every dictionary word at every starting
+    /// position in each Chinese run, overlaps included, rather than a single
+    /// segmentation like [`cut`](MMSeg::cut)/[`cut_simple`](MMSeg::cut_simple).
+    pub fn cut_all(&self, text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let dag = self.build_dag(&chars);
+        let mut pos = 0;
+        let mut ret = Vec::new();
+        while pos < chars.len() {
+            if is_chinese_char(chars[pos]) {
+                for &(end, _freq) in &dag[pos] {
+                    ret.push(chars[pos..end].iter().collect());
+                }
+                pos += 1;
+            } else {
+                let (token, _end) = self.get_ascii_words(&chars, &mut pos);
+                if !token.is_empty() {
+                    ret.push(token);
+                }
+            }
+        }
+        ret
+    }
+
+    /// Like `cut`, but segments each Chinese run with a beam search instead
+    /// of a local three-word lookahead. Uses [`DEFAULT_BEAM_WIDTH`]; see
+    /// [`cut_beam_with_width`](MMSeg::cut_beam_with_width) to configure it.
+    pub fn cut_beam(&self, text: &str) -> Vec<String> {
+        self.cut_beam_with_width(text, DEFAULT_BEAM_WIDTH)
+    }
+
+    /// Like [`cut_beam`](MMSeg::cut_beam) with a configurable beam width.
+    /// `0` is treated as `1`, since it would otherwise discard the search's
+    /// own seed along with every candidate.
+    pub fn cut_beam_with_width(&self, text: &str, beam_width: usize) -> Vec<String> {
+        let beam_width = beam_width.max(1);
+        let mut pos = 0;
+        let chars: Vec<char> = text.chars().collect();
+        let dag = self.build_dag(&chars);
+        let mut ret = Vec::new();
+        while let Some(token) = self.get_next_token_beam(&dag, &chars, &mut pos, beam_width) {
+            ret.push(token);
+        }
+        ret
+    }
+
+    fn get_next_token_beam(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+        beam_width: usize,
+    ) -> Option<String> {
+        while *pos < chars.len() {
+            let chr = chars[*pos];
+            let token = if is_chinese_char(chr) {
+                self.get_chinese_words_beam(dag, chars, pos, beam_width)
+            } else {
+                self.get_ascii_words(chars, pos).0
+            };
+            if !token.is_empty() {
+                return Some(token);
+            }
+        }
+        None
+    }
+
     #[inline]
     fn cut_internal(&self, text: &str, simple: bool) -> Vec<String> {
         let mut pos = 0;
         let chars: Vec<char> = text.chars().collect();
+        let dag = self.build_dag(&chars);
         let mut ret = Vec::new();
         loop {
-            if let Some(token) = self.get_next_token(&chars, &mut pos, simple) {
+            if let Some((token, _end)) = self.get_next_token(&dag, &chars, &mut pos, simple) {
                 ret.push(token);
             } else {
                 break;
@@ -177,26 +332,57 @@ impl MMSeg {
         ret
     }
 
-    fn get_next_token(&self, chars: &[char], pos: &mut usize, simple: bool) -> Option<String> {
+    /// Like `cut`/`cut_simple`, but reports each word's offsets in `text`.
+    pub fn tokenize(&self, text: &str, simple: bool) -> Vec<Token> {
+        let mut pos = 0;
+        let chars: Vec<char> = text.chars().collect();
+        let dag = self.build_dag(&chars);
+        let byte_offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        let mut ret = Vec::new();
+        while let Some((word, end)) = self.get_next_token(&dag, &chars, &mut pos, simple) {
+            let start = end - word.chars().count();
+            let start_byte = byte_offsets[start];
+            let end_byte = byte_offsets.get(end).copied().unwrap_or(text.len());
+            ret.push(Token {
+                word,
+                start,
+                end,
+                start_byte,
+                end_byte,
+            });
+        }
+        ret
+    }
+
+    /// Returns the next token along with its end position (distinct from
+    /// `*pos` for ASCII tokens, since `get_ascii_words` keeps advancing `*pos`
+    /// past trailing separators after the word itself ends).
+    fn get_next_token(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+        simple: bool,
+    ) -> Option<(String, usize)> {
         while *pos < chars.len() {
             let chr = chars[*pos];
-            let token = if is_chinese_char(chr) {
-                if simple {
-                    self.get_chinese_words_simple(&chars, pos)
-                } else {
-                    self.get_chinese_words_complex(&chars, pos)
-                }
+            let (token, end) = if is_chinese_char(chr) {
+                let token = self.get_chinese_words(dag, chars, pos, simple);
+                let end = *pos;
+                (token, end)
             } else {
                 self.get_ascii_words(&chars, pos)
             };
-            if token.len() > 0 {
-                return Some(token);
+            if !token.is_empty() {
+                return Some((token, end));
             }
         }
         None
     }
 
-    fn get_ascii_words(&self, chars: &[char], pos: &mut usize) -> String {
+    /// Returns the matched word along with its end position, since `*pos` keeps
+    /// moving past trailing separators after the word itself ends.
+    fn get_ascii_words(&self, chars: &[char], pos: &mut usize) -> (String, usize) {
         while *pos < chars.len() {
             let chr = chars[*pos];
             if chr.is_ascii_alphanumeric() || is_chinese_char(chr) {
@@ -223,27 +409,180 @@ impl MMSeg {
             *pos += 1;
         }
         // FIXME: avoid allocation
-        chars[start..end].iter().collect()
+        (chars[start..end].iter().collect(), end)
+    }
+
+    fn get_chinese_words(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+        simple: bool,
+    ) -> String {
+        #[cfg(feature = "hmm")]
+        if let Some(run_len) = self.find_oov_run(dag, chars, *pos) {
+            return self.get_chinese_words_hmm(chars, pos, run_len);
+        }
+        if simple {
+            self.get_chinese_words_simple(dag, chars, pos)
+        } else {
+            self.get_chinese_words_complex(dag, chars, pos)
+        }
+    }
+
+    /// Length of the run of Chinese characters starting at `pos` with no
+    /// dictionary matches, if any, so it can go to the HMM segmenter instead
+    /// of being shredded into single characters.
+    #[cfg(feature = "hmm")]
+    fn find_oov_run(&self, dag: &[Vec<(usize, u32)>], chars: &[char], pos: usize) -> Option<usize> {
+        // Check the DAG rather than per-character lookups: a word added via
+        // `add_word` has no single-char entries of its own.
+        if !is_chinese_char(chars[pos]) || !dag[pos].is_empty() {
+            return None;
+        }
+        let mut len = 1;
+        while pos + len < chars.len() && is_chinese_char(chars[pos + len]) && dag[pos + len].is_empty()
+        {
+            len += 1;
+        }
+        Some(len)
+    }
+
+    /// Viterbi-decodes the OOV run and returns its first word, advancing
+    /// `pos` past it; later calls handle the rest of the run.
+    #[cfg(feature = "hmm")]
+    fn get_chinese_words_hmm(&self, chars: &[char], pos: &mut usize, run_len: usize) -> String {
+        let run = &chars[*pos..*pos + run_len];
+        match self.hmm_model.segment(run).first() {
+            Some(&(start, end)) => {
+                *pos += end - start;
+                run[start..end].iter().collect()
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Beam-searches the whole Chinese run starting at `*pos` and returns its
+    /// best path's first word, advancing `pos` past it; later calls handle
+    /// the rest of the run.
+    fn get_chinese_words_beam(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+        beam_width: usize,
+    ) -> String {
+        let start = *pos;
+        let end = chinese_run_end(chars, start);
+        match self.beam_search(dag, chars, start, end, beam_width).into_iter().next() {
+            Some(word) => {
+                *pos += word.chars().count();
+                word
+            }
+            // An empty run can't happen (chinese_run_end(start) > start since
+            // chars[start] is Chinese), so this is an empty best path, which
+            // beam_search never produces for a non-empty run. Advance by one
+            // char anyway so a caller can't be left spinning on `pos`.
+            None => {
+                *pos += 1;
+                String::new()
+            }
+        }
     }
 
-    fn get_chinese_words_simple(&self, chars: &[char], pos: &mut usize) -> String {
-        let chunks = self.create_simple_chunks(chars, pos);
+    /// Beam search over `chars[start..end]`, keeping the top `beam_width`
+    /// paths at each position; returns the highest-scoring complete path.
+    fn beam_search(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        start: usize,
+        end: usize,
+        beam_width: usize,
+    ) -> Vec<String> {
+        let n = end - start;
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut buckets: Vec<BinaryHeap<Sequence>> = (0..=n).map(|_| BinaryHeap::new()).collect();
+        buckets[0].push(Sequence {
+            words: Vec::new(),
+            log_score: 0.0,
+        });
+        for i in 0..n {
+            let global_i = start + i;
+            let mut frontier = std::mem::take(&mut buckets[i]).into_sorted_vec();
+            if frontier.len() > beam_width {
+                frontier = frontier.split_off(frontier.len() - beam_width);
+            }
+            for seq in frontier {
+                let mut p = global_i;
+                for word in self.get_match_chinese_words(dag, chars, &mut p) {
+                    let score = BEAM_WORD_COUNT_PENALTY
+                        + if word.text.is_empty() {
+                            BEAM_ZERO_FREQ_FLOOR
+                        } else if word.freq > 0 {
+                            (word.freq as f32).ln() * BEAM_FREQ_TIEBREAK_WEIGHT
+                        } else {
+                            0.0
+                        };
+                    let next = i + word.len as usize;
+                    let mut words = seq.words.clone();
+                    if word.text.is_empty() {
+                        words.push(Word {
+                            text: chars[global_i..global_i + word.len as usize].iter().collect(),
+                            freq: 0,
+                            len: word.len,
+                        });
+                    } else {
+                        words.push(word);
+                    }
+                    buckets[next].push(Sequence {
+                        words,
+                        log_score: seq.log_score + score,
+                    });
+                }
+            }
+        }
+        match std::mem::take(&mut buckets[n]).into_sorted_vec().pop() {
+            Some(seq) => seq.words.into_iter().map(|w| w.text).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn get_chinese_words_simple(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+    ) -> String {
+        let chunks = self.create_simple_chunks(dag, chars, pos);
         let result = chunks.into_iter().max_by_key(|chk| chk.total_word_len());
         if let Some(chunk) = result {
             let mut ret = String::new();
             for word in chunk.0 {
+                let start = *pos;
+                *pos += word.len as usize;
                 if word.text.is_empty() {
-                    continue;
+                    // No dictionary match at `start`: fall back to the bare
+                    // character, same as `get_chinese_words_beam`, instead of
+                    // leaving `pos` stuck and spinning the caller forever.
+                    ret.extend(&chars[start..*pos]);
+                } else {
+                    ret.push_str(&word.text);
                 }
-                *pos += word.len as usize;
-                ret.push_str(&word.text);
             }
             return ret;
         }
         String::new()
     }
 
-    fn get_chinese_words_complex(&self, chars: &[char], pos: &mut usize) -> String {
+    fn get_chinese_words_complex(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+    ) -> String {
         fn take_high_test<F>(chunks: &mut [Chunk], mut compare: F) -> &mut [Chunk]
         where
             F: FnMut(&Chunk, &Chunk) -> Ordering,
@@ -262,7 +601,7 @@ impl MMSeg {
             &mut chunks[0..i]
         }
 
-        let mut chunks = self.create_chunks(chars, pos);
+        let mut chunks = self.create_chunks(dag, chars, pos);
         let mut chunks = take_high_test(&mut chunks, |a, b| {
             a.total_word_len().cmp(&b.total_word_len())
         });
@@ -285,43 +624,39 @@ impl MMSeg {
         if let Some(chunk) = result {
             let mut ret = String::new();
             for word in chunk.0.iter().take(1) {
+                let start = *pos;
+                *pos += word.len as usize;
                 if word.text.is_empty() {
-                    continue;
+                    // No dictionary match at `start`: fall back to the bare
+                    // character, same as `get_chinese_words_beam`, instead of
+                    // leaving `pos` stuck and spinning the caller forever.
+                    ret.extend(&chars[start..*pos]);
+                } else {
+                    ret.push_str(&word.text);
                 }
-                *pos += word.len as usize;
-                ret.push_str(&word.text);
             }
             return ret;
         }
         String::new()
     }
 
-    fn get_match_chinese_words(&self, chars: &[char], pos: &mut usize) -> Vec<Word> {
-        let mut words = Vec::new();
+    /// Every dictionary word match starting at `*pos`, read out of `dag`
+    /// (see [`MMSeg::build_dag`]).
+    fn get_match_chinese_words(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+    ) -> Vec<Word> {
         let original_pos = *pos;
-        let mut index = 0;
-        while *pos < chars.len() {
-            if index >= self.max_word_len {
-                break;
-            } else if !is_chinese_char(chars[*pos]) {
-                break;
-            }
-            *pos += 1;
-            index += 1;
-            let text: String = chars[original_pos..*pos].iter().collect();
-            let word = self.words.get(&text).map(|v| {
-                let len = text.chars().count();
-                Word {
-                    text: text,
-                    freq: *v,
-                    len: len as u32,
-                }
-            });
-            if let Some(word) = word {
-                words.push(word);
-            }
-        }
-        *pos = original_pos;
+        let mut words: Vec<Word> = dag[original_pos]
+            .iter()
+            .map(|&(end, freq)| Word {
+                text: chars[original_pos..end].iter().collect(),
+                freq,
+                len: (end - original_pos) as u32,
+            })
+            .collect();
         if words.is_empty() {
             // if word not exists , place "X" and length 0
             words.push(Word {
@@ -333,33 +668,66 @@ impl MMSeg {
         words
     }
 
-    fn create_simple_chunks(&self, chars: &[char], pos: &mut usize) -> Vec<Chunk> {
-        let words = self.get_match_chinese_words(chars, pos);
-        let mut chunks = Vec::with_capacity(words.len());
-        for word in words {
-            if word.text.is_empty() {
+    /// For every position, every `(end, freq)` of a dictionary word starting
+    /// there. Built once per `cut`/`cut_simple`/`cut_beam`/`tokenize` call and
+    /// shared by all three segmentation modes through
+    /// [`get_match_chinese_words`](MMSeg::get_match_chinese_words).
+    pub fn build_dag(&self, chars: &[char]) -> Vec<Vec<(usize, u32)>> {
+        let mut dag = Vec::with_capacity(chars.len());
+        let mut i = 0;
+        while i < chars.len() {
+            if !is_chinese_char(chars[i]) {
+                dag.push(Vec::new());
+                i += 1;
                 continue;
             }
-            chunks.push(Chunk::new1(word));
+            // Compute the run's end once and reuse it for every position in
+            // the run, instead of re-scanning the whole run from each
+            // position (that was O(run_length²) for a long run).
+            let run_end = chinese_run_end(chars, i);
+            for j in i..run_end {
+                dag.push(self.words.matches_from(&chars[..run_end], j));
+            }
+            i = run_end;
         }
-        chunks
+        dag
+    }
+
+    fn create_simple_chunks(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+    ) -> Vec<Chunk> {
+        // Keep the no-match placeholder rather than dropping it: dropping it
+        // here left no chunk at all for an unmatched position, so
+        // `get_chinese_words_simple` returned "" without advancing `pos`.
+        self.get_match_chinese_words(dag, chars, pos)
+            .into_iter()
+            .map(Chunk::new1)
+            .collect()
     }
 
-    fn create_chunks(&self, chars: &[char], pos: &mut usize) -> Vec<Chunk> {
+    fn create_chunks(
+        &self,
+        dag: &[Vec<(usize, u32)>],
+        chars: &[char],
+        pos: &mut usize,
+    ) -> Vec<Chunk> {
         let mut chunks = Vec::new();
         let original_pos = *pos;
         let text_len = chars.len();
-        let words1 = self.get_match_chinese_words(chars, pos);
+        let words1 = self.get_match_chinese_words(dag, chars, pos);
         for word1 in words1 {
             let word1_len = word1.len as usize;
             *pos += word1_len;
             if *pos < text_len {
-                let words2 = self.get_match_chinese_words(chars, pos);
+                let words2 = self.get_match_chinese_words(dag, chars, pos);
                 for word2 in words2 {
                     let word2_len = word2.len as usize;
                     *pos += word2_len;
                     if *pos < text_len {
-                        let words3 = self.get_match_chinese_words(chars, pos);
+                        let words3 = self.get_match_chinese_words(dag, chars, pos);
                         for word3 in words3 {
                             if word3.text.is_empty() {
                                 chunks.push(Chunk::new2(word1.clone(), word2.clone()));
@@ -386,3 +754,12 @@ fn is_chinese_char(chr: char) -> bool {
     let chr = chr as u32;
     chr >= 0x4e00 && chr < 0x9fa6
 }
+
+/// End of the contiguous run of Chinese characters starting at `pos` (exclusive).
+fn chinese_run_end(chars: &[char], pos: usize) -> usize {
+    let mut end = pos;
+    while end < chars.len() && is_chinese_char(chars[end]) {
+        end += 1;
+    }
+    end
+}
@@ -0,0 +1,73 @@
+//! Character-keyed prefix trie backing `MMSeg`'s dictionary.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub(crate) struct TrieNode {
+    children: HashMap<char, TrieNode>,
+    freq: Option<u32>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Trie {
+    root: TrieNode,
+}
+
+impl Trie {
+    pub(crate) fn new() -> Self {
+        Trie::default()
+    }
+
+    pub(crate) fn insert(&mut self, word: &str, freq: u32) {
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.freq = Some(freq);
+    }
+
+    /// Removes `word`, returning whether it was present.
+    pub(crate) fn remove(&mut self, word: &str) -> bool {
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            match node.children.get_mut(&c) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        node.freq.take().is_some()
+    }
+
+    /// Max frequency among single-character words; used by `suggest_freq`'s
+    /// tie-break.
+    pub(crate) fn max_single_char_freq(&self) -> u32 {
+        self.root
+            .children
+            .values()
+            .filter_map(|child| child.freq)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Every `(end, freq)` pair reachable by descending the trie from
+    /// `chars[pos..]`, stopping as soon as either `chars` or the trie runs
+    /// out.
+    pub(crate) fn matches_from(&self, chars: &[char], pos: usize) -> Vec<(usize, u32)> {
+        let mut matches = Vec::new();
+        let mut node = &self.root;
+        let mut i = pos;
+        while i < chars.len() {
+            match node.children.get(&chars[i]) {
+                Some(child) => {
+                    node = child;
+                    i += 1;
+                    if let Some(freq) = node.freq {
+                        matches.push((i, freq));
+                    }
+                }
+                None => break,
+            }
+        }
+        matches
+    }
+}
@@ -1,6 +1,6 @@
 extern crate mmseg;
 
-use mmseg::MMSeg;
+use mmseg::{KeywordExtract, MMSeg, TextRank, TfIdf};
 
 #[test]
 fn test_mmseg() {
@@ -11,3 +11,156 @@ fn test_mmseg() {
         .cut("我是拖拉机学院手扶拖拉机专业的。不用多久，我就会升职加薪，当上CEO，走上人生巅峰。");
     println!("complex: {:#?}", complex);
 }
+
+#[test]
+fn test_add_remove_word() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("拖拉机学院", None);
+    assert_eq!(mmseg.cut("拖拉机学院"), vec!["拖拉机学院".to_string()]);
+
+    mmseg.remove_word("拖拉机学院");
+    assert!(!mmseg
+        .cut_all("拖拉机学院")
+        .contains(&"拖拉机学院".to_string()));
+}
+
+#[test]
+fn test_suggest_freq() {
+    let mmseg = MMSeg::new();
+    assert_eq!(mmseg.suggest_freq("拖拉机学院"), 0);
+    assert!(mmseg.suggest_freq("的") > 0);
+}
+
+#[test]
+fn test_cut_beam() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("拖拉机学院", None);
+    assert_eq!(
+        mmseg.cut_beam("拖拉机学院"),
+        vec!["拖拉机学院".to_string()]
+    );
+
+    // A beam width of 0 would previously discard the whole search frontier,
+    // including its own seed, and hang forever instead of returning.
+    assert_eq!(
+        mmseg.cut_beam_with_width("拖拉机学院", 0),
+        vec!["拖拉机学院".to_string()]
+    );
+}
+
+#[test]
+fn test_cut_beam_prefers_known_word_over_high_freq_singles() {
+    // "你好" is a real (freq-0) dictionary word, same as every multi-char
+    // entry loaded from words_dict. Scoring it purely by ln(freq) used to
+    // tie it with the "no match" placeholder, so two very common single
+    // characters would always win by summing their own high frequencies.
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("你", Some(1_000_000));
+    mmseg.add_word("好", Some(1_000_000));
+    mmseg.add_word("你好", None);
+
+    assert_eq!(mmseg.cut("你好"), vec!["你好".to_string()]);
+    assert_eq!(mmseg.cut_beam("你好"), vec!["你好".to_string()]);
+    assert_eq!(
+        mmseg.cut_beam_with_width("你好", 16),
+        vec!["你好".to_string()]
+    );
+}
+
+#[test]
+fn test_cut_all() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("拖拉机", None);
+    mmseg.add_word("拖拉机学院", None);
+
+    let words = mmseg.cut_all("拖拉机学院 abc");
+    // Unlike cut()/cut_simple(), cut_all() returns every overlapping match.
+    assert!(words.contains(&"拖拉机".to_string()));
+    assert!(words.contains(&"拖拉机学院".to_string()));
+    assert!(words.contains(&"abc".to_string()));
+}
+
+#[test]
+fn test_oov_run_does_not_hang() {
+    // Without the embed-dict feature, MMSeg::new() starts empty, so a
+    // dictionary that only knows these two words leaves "是个好地方" entirely
+    // out-of-vocabulary. Without the hmm feature this used to spin forever
+    // instead of falling back to single characters.
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("中国", None);
+    mmseg.add_word("新疆", None);
+    let text = "中国新疆是个好地方";
+
+    assert_eq!(mmseg.cut(text).join(""), text);
+    assert_eq!(mmseg.cut_simple(text).join(""), text);
+    let tokens: Vec<String> = mmseg
+        .tokenize(text, false)
+        .into_iter()
+        .map(|t| t.word)
+        .collect();
+    assert_eq!(tokens.join(""), text);
+}
+
+#[cfg(feature = "hmm")]
+#[test]
+fn test_oov_run_uses_hmm_segmentation() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("中国", None);
+    mmseg.add_word("新疆", None);
+
+    let words = mmseg.cut("中国新疆是个好地方");
+    assert!(words.contains(&"中国".to_string()));
+    assert!(words.contains(&"新疆".to_string()));
+    // Without the embed-dict feature the tail is out-of-vocabulary, and the
+    // HMM fallback should recover at least one multi-character word out of
+    // it rather than shredding it into single characters. With embed-dict,
+    // the dictionary matches the tail and the HMM is not reached.
+    assert!(words
+        .iter()
+        .skip_while(|w| *w != "新疆")
+        .skip(1)
+        .any(|w| w.chars().count() > 1));
+}
+
+#[test]
+fn test_tfidf_extract_tags() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("拖拉机学院", None);
+    mmseg.add_word("人生巅峰", None);
+    let text = "我是拖拉机学院手扶拖拉机专业的。不用多久，我就会升职加薪，当上CEO，走上人生巅峰。";
+
+    // top_k is larger than the text's distinct multi-char terms: with the
+    // hmm feature enabled, OOV runs get recombined into several other
+    // multi-char terms tied on frequency, so a small top_k could cut off
+    // either dictionary word depending on HashMap iteration order.
+    let tfidf = TfIdf::new(&mmseg);
+    let tags = tfidf.extract_tags(text, 20, &[]);
+    assert!(!tags.is_empty());
+    assert!(tags.iter().any(|(word, _)| word == "拖拉机学院"));
+    assert!(tags.iter().any(|(word, _)| word == "人生巅峰"));
+}
+
+#[test]
+fn test_textrank_extract_tags() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("拖拉机学院", None);
+    mmseg.add_word("人生巅峰", None);
+    let text = "我是拖拉机学院手扶拖拉机专业的。不用多久，我就会升职加薪，当上CEO，走上人生巅峰。";
+
+    let textrank = TextRank::new(&mmseg);
+    let tags = textrank.extract_tags(text, 3, &[]);
+    assert!(!tags.is_empty());
+}
+
+#[test]
+fn test_tokenize_offsets() {
+    let mut mmseg = MMSeg::new();
+    mmseg.add_word("你好", None);
+    let text = "hello, 你好 world!";
+    let chars: Vec<char> = text.chars().collect();
+    for token in mmseg.tokenize(text, false) {
+        let from_chars: String = chars[token.start..token.end].iter().collect();
+        assert_eq!(from_chars, token.word);
+        assert_eq!(&text[token.start_byte..token.end_byte], token.word);
+    }
+}